@@ -32,9 +32,17 @@
 #![no_std]
 #![deny(missing_docs)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
 use core::borrow::Borrow;
 use core::fmt;
+use core::marker::PhantomData;
 use core::ops::{Deref, DerefMut};
+use core::pin::Pin;
+use core::ptr::NonNull;
 
 /// A mutable reference to an `Option` that is guaranteed to always be `Some`.
 pub struct SomeMut<'a, T>(
@@ -52,6 +60,22 @@ pub trait OptionExt<T>: sealed::Sealed {
     ///
     /// See also [`Option::as_mut()`] if [`take()`][Option::take]ing isn't required.
     fn some_mut(&mut self) -> Option<SomeMut<'_, T>>;
+
+    /// Insert `value` into the option, then return a [`SomeMut`] pointing at it.
+    ///
+    /// Like [`Option::insert()`], the option is always overwritten and is always `Some`
+    /// afterwards, but you get back the take-capable handle rather than a bare `&mut T`.
+    fn insert_some_mut(&mut self, value: T) -> SomeMut<'_, T>;
+
+    /// Return a [`SomeMut`] for the contained value, inserting `value` first if `None`.
+    ///
+    /// Like [`Option::get_or_insert()`], but returns the take-capable handle.
+    fn get_or_insert_some_mut(&mut self, value: T) -> SomeMut<'_, T>;
+
+    /// Return a [`SomeMut`] for the contained value, inserting the result of `f` first if `None`.
+    ///
+    /// Like [`Option::get_or_insert_with()`], but returns the take-capable handle.
+    fn get_or_insert_some_mut_with(&mut self, f: impl FnOnce() -> T) -> SomeMut<'_, T>;
 }
 
 impl<T> sealed::Sealed for Option<T> {}
@@ -62,6 +86,22 @@ impl<T> OptionExt<T> for Option<T> {
             None => None,
         }
     }
+
+    fn insert_some_mut(&mut self, value: T) -> SomeMut<'_, T> {
+        *self = Some(value);
+        SomeMut(self)
+    }
+
+    fn get_or_insert_some_mut(&mut self, value: T) -> SomeMut<'_, T> {
+        self.get_or_insert_some_mut_with(|| value)
+    }
+
+    fn get_or_insert_some_mut_with(&mut self, f: impl FnOnce() -> T) -> SomeMut<'_, T> {
+        if self.is_none() {
+            *self = Some(f());
+        }
+        SomeMut(self)
+    }
 }
 
 impl<'a, T> SomeMut<'a, T> {
@@ -105,6 +145,218 @@ impl<'a, T> SomeMut<'a, T> {
     pub fn into_option_mut(self) -> &'a mut Option<T> {
         self.0
     }
+
+    /// Replace the value in this `SomeMut` with `value`, returning the old value.
+    ///
+    /// Like [`Option::replace()`], but the slot is guaranteed to stay `Some`, so
+    /// there's no `Option` wrapping the returned old value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use some_mut::OptionExt;
+    ///
+    /// let mut x = Some(2);
+    /// let old = x.some_mut().unwrap().replace(5);
+    /// assert_eq!(old, 2);
+    /// assert_eq!(x, Some(5));
+    /// ```
+    pub fn replace(self, value: T) -> T {
+        // SAFETY: safety invariant on SomeMut
+        unsafe { self.0.replace(value).unwrap_unchecked() }
+    }
+
+    /// Swap the values of two `SomeMut`s.
+    ///
+    /// Both options stay `Some` afterwards.
+    pub fn swap(&mut self, other: &mut SomeMut<'_, T>) {
+        core::mem::swap(&mut **self, &mut **other)
+    }
+
+    /// Replace the value in this `SomeMut` with the result of applying `f` to the old value.
+    ///
+    /// The value is moved out, `f` is run, and its result is written back.
+    ///
+    /// # Panics
+    ///
+    /// If `f` unwinds, the original option is left `None`. This is the only way the
+    /// `Some` invariant is ever broken, and it cannot be observed safely: the `SomeMut`
+    /// is consumed by this call, and the unwind propagates.
+    pub fn replace_with(self, f: impl FnOnce(T) -> T) {
+        let value = self.0.take();
+        // SAFETY: safety invariant on SomeMut
+        let value = unsafe { value.unwrap_unchecked() };
+        *self.0 = Some(f(value));
+    }
+
+    /// Project this `SomeMut` into one of the value's subfields.
+    ///
+    /// Analogous to [`RefMut::map`][core::cell::RefMut::map]: the returned [`MappedSomeMut`]
+    /// keeps the original option borrowed for `'a` while dereferencing to `U`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use some_mut::OptionExt;
+    ///
+    /// let mut x = Some((1, 2));
+    /// let mut field = x.some_mut().unwrap().map(|t| &mut t.1);
+    /// *field += 10;
+    /// assert_eq!(x, Some((1, 12)));
+    /// ```
+    pub fn map<U>(self, f: impl FnOnce(&mut T) -> &mut U) -> MappedSomeMut<'a, T, U> {
+        let opt = self.0;
+        // SAFETY: safety invariant on SomeMut
+        let value = NonNull::from(f(unsafe { opt.as_mut().unwrap_unchecked() }));
+        MappedSomeMut {
+            opt,
+            value,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// A [`SomeMut`] projected into one of its value's subfields, via [`SomeMut::map()`].
+///
+/// Dereferences to the projected `U`. Because a subfield cannot be [`take()`][SomeMut::take]n
+/// on its own, this guard intentionally offers no `take()`; use [`into_option_mut()`] to get
+/// back to the source option if you need to clear the whole slot.
+///
+/// [`into_option_mut()`]: MappedSomeMut::into_option_mut
+pub struct MappedSomeMut<'a, T, U> {
+    /// INVARIANT: must always be Option::Some
+    opt: &'a mut Option<T>,
+    /// Points into the subfield of `*opt`'s value selected by the projection function.
+    value: NonNull<U>,
+    _marker: PhantomData<&'a mut U>,
+}
+
+impl<'a, T, U> MappedSomeMut<'a, T, U> {
+    /// Unwrap this guard into a mutable reference to the projected subfield, tied to `'a`.
+    pub fn into_mut(self) -> &'a mut U {
+        // SAFETY: the pointer was derived from a `&mut U` borrow of `*self.opt` valid for `'a`,
+        // and `self` holds that borrow for its whole lifetime.
+        unsafe { &mut *self.value.as_ptr() }
+    }
+
+    /// Unwrap this guard back into a mutable reference to the original option.
+    pub fn into_option_mut(self) -> &'a mut Option<T> {
+        self.opt
+    }
+}
+
+impl<T, U> Deref for MappedSomeMut<'_, T, U> {
+    type Target = U;
+    fn deref(&self) -> &U {
+        // SAFETY: see `MappedSomeMut::into_mut`
+        unsafe { self.value.as_ref() }
+    }
+}
+
+impl<T, U> DerefMut for MappedSomeMut<'_, T, U> {
+    fn deref_mut(&mut self) -> &mut U {
+        // SAFETY: see `MappedSomeMut::into_mut`
+        unsafe { self.value.as_mut() }
+    }
+}
+
+impl<T, U: fmt::Debug> fmt::Debug for MappedSomeMut<'_, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<T, U: fmt::Display> fmt::Display for MappedSomeMut<'_, T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A [`Pin<&mut T>`][Pin] into an `Option` that is guaranteed to always be `Some`.
+///
+/// This is the pinned analogue of [`SomeMut`], obtained via [`PinOptionExt::some_mut_pinned()`].
+/// It lets you drive a sub-future buffered in an option without ever observing a `None`.
+pub struct PinSomeMut<'a, T>(
+    /// INVARIANT: must always be Option::Some
+    Pin<&'a mut Option<T>>,
+);
+
+/// An extension trait that allows one to obtain a [`PinSomeMut`] from a pinned option.
+pub trait PinOptionExt<'a, T>: sealed::Sealed {
+    /// Obtain a `PinSomeMut<T>` from a `Pin<&mut Option<T>>`.
+    fn some_mut_pinned(self) -> Option<PinSomeMut<'a, T>>;
+}
+
+impl<T> sealed::Sealed for Pin<&'_ mut Option<T>> {}
+impl<'a, T> PinOptionExt<'a, T> for Pin<&'a mut Option<T>> {
+    fn some_mut_pinned(self) -> Option<PinSomeMut<'a, T>> {
+        if self.is_some() {
+            Some(PinSomeMut(self))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, T> PinSomeMut<'a, T> {
+    /// Obtain a pinned mutable reference to the contained value.
+    ///
+    /// This is a safe pin projection into the guaranteed-`Some` slot, suitable for e.g.
+    /// calling [`Future::poll`][core::future::Future::poll].
+    pub fn as_pin_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: we never move out of the pinned option, and the safety invariant on
+        // PinSomeMut guarantees the slot is Some.
+        unsafe {
+            let opt = self.0.as_mut().get_unchecked_mut();
+            Pin::new_unchecked(opt.as_mut().unwrap_unchecked())
+        }
+    }
+
+    /// Consume this `PinSomeMut` into a pinned reference tied to the original option's lifetime.
+    pub fn get_pin_mut(self) -> Pin<&'a mut T> {
+        // SAFETY: as for `as_pin_mut`, relying on the Some invariant.
+        unsafe {
+            let opt = self.0.get_unchecked_mut();
+            Pin::new_unchecked(opt.as_mut().unwrap_unchecked())
+        }
+    }
+
+    /// Clear the original option, dropping the contained value in place.
+    ///
+    /// Unlike [`take()`][PinSomeMut::take] this is available for `!Unpin` types, since the
+    /// value is dropped where it sits rather than moved out.
+    pub fn clear(self) {
+        // SAFETY: setting the option to None drops the pinned value in place; it is never moved.
+        unsafe {
+            *self.0.get_unchecked_mut() = None;
+        }
+    }
+}
+
+impl<'a, T: Unpin> PinSomeMut<'a, T> {
+    /// Take the value from the pinned option, leaving a `None` behind.
+    ///
+    /// Only available for [`Unpin`] types, since moving the value out of a pinned slot is
+    /// otherwise unsound.
+    pub fn take(self) -> T {
+        // SAFETY: T: Unpin, so moving out of the pinned slot is sound; the Some invariant
+        // guarantees the unwrap.
+        unsafe { self.0.get_unchecked_mut().take().unwrap_unchecked() }
+    }
+}
+
+impl<T> Deref for PinSomeMut<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        // SAFETY: safety invariant on PinSomeMut
+        unsafe { self.0.as_ref().get_ref().as_ref().unwrap_unchecked() }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PinSomeMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        (**self).fmt(f)
+    }
 }
 
 impl<T> Deref for SomeMut<'_, T> {
@@ -163,3 +415,52 @@ impl<T: PartialOrd> PartialOrd<T> for SomeMut<'_, T> {
         T::partial_cmp(self, other)
     }
 }
+
+/// A guaranteed-present, removable mutable handle.
+///
+/// This abstracts over guard types that wrap a value which is known to be present and can be
+/// both removed ([`take`][TakeRef::take]) or borrowed out ([`into_mut`][TakeRef::into_mut]) for
+/// the original `'a`. It lets generic code work uniformly over [`SomeMut`] and, behind the
+/// `std`/`alloc` features, the standard collections' occupied entry types.
+pub trait TakeRef<'a> {
+    /// The type of the wrapped value.
+    type Value: 'a;
+
+    /// Remove and return the wrapped value.
+    fn take(self) -> Self::Value;
+
+    /// Borrow the wrapped value mutably for the rest of `'a`.
+    fn into_mut(self) -> &'a mut Self::Value;
+}
+
+impl<'a, T> TakeRef<'a> for SomeMut<'a, T> {
+    type Value = T;
+    fn take(self) -> T {
+        SomeMut::take(self)
+    }
+    fn into_mut(self) -> &'a mut T {
+        SomeMut::into_mut(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, K, V> TakeRef<'a> for std::collections::hash_map::OccupiedEntry<'a, K, V> {
+    type Value = V;
+    fn take(self) -> V {
+        self.remove()
+    }
+    fn into_mut(self) -> &'a mut V {
+        self.into_mut()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, K: Ord, V> TakeRef<'a> for alloc::collections::btree_map::OccupiedEntry<'a, K, V> {
+    type Value = V;
+    fn take(self) -> V {
+        self.remove()
+    }
+    fn into_mut(self) -> &'a mut V {
+        self.into_mut()
+    }
+}